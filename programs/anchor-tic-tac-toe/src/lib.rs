@@ -9,7 +9,11 @@ declare_id!("9eSXvKJh3tChbzBXKSUKHYABsK7z2YzYrjGq534PueYC");
 const EMPTY: u8 = 0;
 const PLAYER_X_MARK: u8 = 1;
 const PLAYER_O_MARK: u8 = 2;
-const BOARD_SIZE: usize = 9; // 3x3 grid = 9 cells
+const MAX_BOARD_DIM: u8 = 5; // largest supported board edge length (5x5)
+const MAX_CELLS: usize = MAX_BOARD_DIM as usize * MAX_BOARD_DIM as usize;
+// Floor on `claim_timeout`'s caller-supplied `timeout_secs`, so the waiting
+// player can't claim a forfeit the instant a new timestamp is observed.
+const MIN_TIMEOUT_SECS: i64 = 60;
 
 
 #[event]
@@ -35,6 +39,24 @@ pub struct GameDraw {
     pub game_id: u64,
 }
 
+#[event]
+pub struct GameForfeited {
+    pub game_id: u64,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+}
+
+#[event]
+pub struct JoinRequested {
+    pub game_id: u64,
+    pub requester: Pubkey,
+}
+
+#[event]
+pub struct GameRestarted {
+    pub game_id: u64,
+}
+
 #[error_code]
 pub enum TicTacError {
     #[msg("Player is already registered")]
@@ -61,60 +83,118 @@ pub enum TicTacError {
     Unauthorised,
     #[msg("Player O has not joined")]
     PlayerONotRegistered,
+    #[msg("The timeout period has not elapsed yet")]
+    InvalidTimestamp,
+    #[msg("The board dimension is outside the supported range")]
+    InvalidBoardDimension,
+    #[msg("The winning run length must be between 1 and the board dimension")]
+    InvalidWinLength,
+    #[msg("The game is still in progress")]
+    GameStillActive,
+    #[msg("The requested timeout is shorter than the minimum allowed")]
+    TimeoutTooShort,
 }
 
 /// Checks if a player with the given mark has won the game.
 ///
+/// Scans every cell as a potential run origin and looks `win_len` cells out
+/// along each of the four directions (horizontal, vertical, and both
+/// diagonals), bounds-checked against `dim`.
+///
 /// # Arguments
-/// * `board` - Reference to the game board array
+/// * `board` - Flat row-major board storage, length `dim * dim`
+/// * `dim` - The board's edge length
+/// * `win_len` - Number of consecutive matching marks required to win
 /// * `mark` - The player's mark (PLAYER_X_MARK or PLAYER_O_MARK)
 ///
 /// # Returns
-/// `true` if the player has a winning combination, `false` otherwise
-fn check_winner(board: &[u8; BOARD_SIZE], mark: u8) -> bool {
-    const WINNING_COMBINATIONS: [[usize; 3]; 8] = [
-        [0, 1, 2],
-        [3, 4, 5],
-        [6, 7, 8],
-        [0, 3, 6],
-        [1, 4, 7],
-        [2, 5, 8],
-        [0, 4, 8],
-        [2, 4, 6],
-    ];
-    WINNING_COMBINATIONS
-        .iter()
-        .any(|combo| board[combo[0]] == mark && board[combo[1]] == mark && board[combo[2]] == mark)
+/// `true` if the player has a winning run, `false` otherwise
+fn check_winner(board: &[u8], dim: u8, win_len: u8, mark: u8) -> bool {
+    let dim = dim as isize;
+    let win_len = win_len as isize;
+    let cell = |r: isize, c: isize| -> Option<u8> {
+        if r < 0 || c < 0 || r >= dim || c >= dim {
+            None
+        } else {
+            Some(board[(r * dim + c) as usize])
+        }
+    };
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+    for r in 0..dim {
+        for c in 0..dim {
+            for (dr, dc) in DIRECTIONS {
+                let run_matches = (0..win_len).all(|i| cell(r + dr * i, c + dc * i) == Some(mark));
+                if run_matches {
+                    return true;
+                }
+            }
+        }
+    }
+    false
 }
 
 /// Checks if the board is completely filled (no empty cells remaining).
 ///
 /// # Arguments
-/// * `board` - Reference to the game board array
+/// * `board` - Flat row-major board storage
 ///
 /// # Returns
 /// `true` if all cells are occupied, `false` if any cell is empty
-fn is_board_full(board: &[u8; BOARD_SIZE]) -> bool {
+fn is_board_full(board: &[u8]) -> bool {
     board.iter().all(|&cell| cell != EMPTY)
 }
 #[ephemeral]
 #[program]
 pub mod tic_tac {
     use super::*;
-    
-    /// Initializes a new tic-tac-toe game.
+
+    /// Initializes the singleton dashboard used to index all games.
+    ///
+    /// Callable once; tracks the total number of games created and the most
+    /// recently created board, so a frontend or matchmaker can discover
+    /// activity without scanning every `UserGameCounter`. Only updated from
+    /// L1 instructions (`initialize`), since a delegated board's moves,
+    /// forfeits, and restarts happen on the ephemeral rollup where this
+    /// non-delegated account can't be written.
+    pub fn initialize_dashboard(ctx: Context<InitializeDashboard>) -> Result<()> {
+        let dashboard = &mut ctx.accounts.dashboard;
+        dashboard.game_count = 0;
+        dashboard.latest_game = Pubkey::default();
+        Ok(())
+    }
+
+    /// Initializes a new m,n,k-game board.
     ///
     /// Creates a new game board and sets the caller as Player X. The game starts
     /// in an active state waiting for Player O to join. A new PDA account is
     /// created for the board using the payer's public key and game count.
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    ///
+    /// # Arguments
+    /// * `board_dim` - Edge length of the square board (1..=`MAX_BOARD_DIM`)
+    /// * `win_len` - Number of marks in a row required to win (1..=`board_dim`)
+    pub fn initialize(ctx: Context<Initialize>, board_dim: u8, win_len: u8) -> Result<()> {
+        require!(
+            board_dim > 0 && board_dim <= MAX_BOARD_DIM,
+            TicTacError::InvalidBoardDimension
+        );
+        require!(
+            win_len > 0 && win_len <= board_dim,
+            TicTacError::InvalidWinLength
+        );
+
+        let board_key = ctx.accounts.board_account.key();
         let board = &mut ctx.accounts.board_account;
         board.player_x = ctx.accounts.payer.key();
         board.player_o = Pubkey::default(); //111111111....1
+        board.pending_o = Pubkey::default();
         board.winner_address = Pubkey::default();
         board.current_player = board.player_x;
-        board.board = [EMPTY; BOARD_SIZE as usize];
-        board.is_active = true;
+        board.board = vec![EMPTY; board_dim as usize * board_dim as usize];
+        board.board_dim = board_dim;
+        board.win_len = win_len;
+        board.state = GameState::Waiting;
+        board.last_move_ts = Clock::get()?.unix_timestamp;
 
         board.game_id = ctx.accounts.user_games.game_count; // 0
         ctx.accounts.user_games.game_count =
@@ -124,38 +204,71 @@ pub mod tic_tac {
             player_x: board.player_x,
         });
 
+        let dashboard = &mut ctx.accounts.dashboard;
+        dashboard.game_count = dashboard.game_count.checked_add(1).unwrap();
+        dashboard.latest_game = board_key;
+
         Ok(())
     }
 
-    /// Registers Player O to join an existing game.
+    /// Requests to join an existing game as Player O.
     ///
-    /// Allows a second player to register as Player O for a game created by Player X.
-    /// This must be called before any moves can be made. Player O cannot be the same
-    /// as Player X, and the game must be active (not finished).
+    /// Records the signer as the *pending* Player O requester; they are not
+    /// seated until Player X calls [`accept_player_o`]. This lets the game
+    /// creator control who joins instead of the slot being grabbed by the
+    /// first arbitrary wallet to call in.
     ///
     /// # Requirements
-    /// * Game must be active (`is_active == true`)
-    /// * Player O slot must be empty (`player_o == Pubkey::default()`)
-    /// * Player O cannot be the same as Player X
+    /// * Game must still be waiting for a Player O (`state == GameState::Waiting`)
+    /// * No join request already pending (`pending_o == Pubkey::default()`)
+    /// * The requester cannot be Player X
     pub fn player_o_register(ctx: Context<RegisterPlayerO>) -> Result<()> {
-        // require!(
-        //     ctx.accounts.board_account.is_active,
-        //     TicTacError::GameOver
-        // );
-
         let board = &mut ctx.accounts.board_account;
-        let player_o_key = ctx.accounts.player_o.key();
+        let requester_key = ctx.accounts.player_o.key();
 
         require!(
-            board.player_x != player_o_key,
+            board.player_x != requester_key,
             TicTacError::PlayerAlreadyRegistered
         );
-        // require!(
-        //     board.player_o == Pubkey::default() && board.player_x != player_o_key,
-        //     TicTacError::PlayerAlreadyRegistered
-        // );
 
-        board.player_o = player_o_key;
+        board.pending_o = requester_key;
+        emit!(JoinRequested {
+            game_id: board.game_id,
+            requester: requester_key,
+        });
+        Ok(())
+    }
+
+    /// Accepts the pending Player O join request.
+    ///
+    /// Callable only by Player X. Promotes `pending_o` into the seated
+    /// `player_o` and starts the game, allowing moves to begin.
+    pub fn accept_player_o(ctx: Context<AcceptPlayerO>) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.pending_o != Pubkey::default(),
+            TicTacError::PlayerONotRegistered
+        );
+
+        board.player_o = board.pending_o;
+        board.pending_o = Pubkey::default();
+        board.state = GameState::XMove;
+        board.last_move_ts = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// Rejects the pending Player O join request.
+    ///
+    /// Callable only by Player X. Clears `pending_o` so another wallet can
+    /// request to join.
+    pub fn reject_player_o(ctx: Context<RejectPlayerO>) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.pending_o != Pubkey::default(),
+            TicTacError::PlayerONotRegistered
+        );
+
+        board.pending_o = Pubkey::default();
         Ok(())
     }
 
@@ -165,11 +278,11 @@ pub mod tic_tac {
     /// Player O must have previously registered using `player_o_register`.
     ///
     /// # Requirements
-    /// * Game must be active (`is_active == true`)
+    /// * Game must be active (`state.is_active() == true`)
     /// * Signer must match the registered Player O
     pub fn player_o_join(ctx: Context<PlayerOJoin>) -> Result<()> {
         require!(
-            ctx.accounts.board_account.is_active == true,
+            ctx.accounts.board_account.state.is_active(),
             TicTacError::GameOver
         );
         let board = &ctx.accounts.board_account;
@@ -190,10 +303,13 @@ pub mod tic_tac {
     /// is set to inactive. Otherwise, the turn switches to the other player.
     ///
     /// # Arguments
-    /// * `position` - The board position (0-8) where the move should be made
-    ///   - Positions are laid out as: 0|1|2, 3|4|5, 6|7|8
+    /// * `position` - The flat row-major board position (0..`board_dim * board_dim`)
     pub fn make_move(ctx: Context<PlayerMove>, position: u8) -> Result<()> {
-        require!(position < BOARD_SIZE as u8, TicTacError::InvalidPosition);
+        let board_dim = ctx.accounts.board_account.board_dim;
+        require!(
+            (position as u16) < board_dim as u16 * board_dim as u16,
+            TicTacError::InvalidPosition
+        );
         let board = &mut ctx.accounts.board_account;
         let player_key = ctx.accounts.player.key();
         let index = position as usize;
@@ -209,21 +325,26 @@ pub mod tic_tac {
         };
 
         board.board[index] = mark;
+        board.last_move_ts = Clock::get()?.unix_timestamp;
         emit!(MoveMade{
             player:player_key,
             position:position,
             game_id:board.game_id
         });
 
-        if check_winner(&board.board, mark) {
+        if check_winner(&board.board, board.board_dim, board.win_len, mark) {
             board.winner_address = player_key;
-            board.is_active = false;
+            board.state = if mark == PLAYER_X_MARK {
+                GameState::XWon
+            } else {
+                GameState::OWon
+            };
             emit!(GameWon {
                 game_id: board.game_id,
                 winner: player_key
             });
         } else if is_board_full(&board.board) {
-            board.is_active = false;
+            board.state = GameState::Draw;
             emit!(GameDraw {
                 game_id: board.game_id
             });
@@ -233,10 +354,84 @@ pub mod tic_tac {
             } else {
                 board.player_x
             };
+            board.state = if board.current_player == board.player_x {
+                GameState::XMove
+            } else {
+                GameState::OMove
+            };
         }
         Ok(())
     }
 
+    /// Claims a forfeit win when the opponent has gone quiet for too long.
+    ///
+    /// Callable by either registered player as long as it is not currently
+    /// their own turn. If more than `timeout_secs` have elapsed since
+    /// `last_move_ts`, the game is ended in the caller's favour.
+    ///
+    /// # Arguments
+    /// * `timeout_secs` - How many seconds of inactivity must have elapsed
+    ///   since the opponent's turn began (`last_move_ts`) before a forfeit
+    ///   can be claimed.
+    ///   Must be at least `MIN_TIMEOUT_SECS`.
+    pub fn claim_timeout(ctx: Context<ClaimTimeout>, timeout_secs: i64) -> Result<()> {
+        require!(
+            timeout_secs >= MIN_TIMEOUT_SECS,
+            TicTacError::TimeoutTooShort
+        );
+
+        let board = &mut ctx.accounts.board_account;
+        let claimant_key = ctx.accounts.claimant.key();
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now - board.last_move_ts > timeout_secs,
+            TicTacError::InvalidTimestamp
+        );
+
+        let loser = if claimant_key == board.player_x {
+            board.player_o
+        } else {
+            board.player_x
+        };
+
+        board.winner_address = claimant_key;
+        board.state = if claimant_key == board.player_x {
+            GameState::XWon
+        } else {
+            GameState::OWon
+        };
+        emit!(GameForfeited {
+            game_id: board.game_id,
+            winner: claimant_key,
+            loser,
+        });
+
+        Ok(())
+    }
+
+    /// Restarts a concluded game for another round on the same board PDA.
+    ///
+    /// Callable by either registered player once the game has ended. Resets
+    /// the grid and game state but keeps both registered players, so a pair
+    /// can play a best-of-N series without paying rent for a fresh account
+    /// each time.
+    pub fn restart(ctx: Context<Restart>) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+
+        board.board = vec![EMPTY; board.board_dim as usize * board.board_dim as usize];
+        board.winner_address = Pubkey::default();
+        board.state = GameState::XMove;
+        board.current_player = board.player_x;
+        board.last_move_ts = Clock::get()?.unix_timestamp;
+
+        emit!(GameRestarted {
+            game_id: board.game_id
+        });
+
+        Ok(())
+    }
+
     /// Delegates the game board account to MagicBlock's Ephemeral Rollup.
     ///
     /// This transfers the board account to an ephemeral rollup validator, enabling
@@ -304,19 +499,62 @@ pub struct DelegateBoard<'info> {
 }
 
 
+/// Lifecycle of a game, replacing a lone `is_active` bool so illegal
+/// transitions (e.g. moving before Player O has joined) are impossible
+/// by construction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum GameState {
+    /// Created by Player X, waiting for Player O to be accepted.
+    Waiting,
+    /// Player X to move.
+    XMove,
+    /// Player O to move.
+    OMove,
+    /// Player X won.
+    XWon,
+    /// Player O won.
+    OWon,
+    /// Board filled with no winner.
+    Draw,
+}
+
+impl GameState {
+    /// `true` while moves can still be made (it is X's or O's turn).
+    fn is_active(&self) -> bool {
+        matches!(self, GameState::XMove | GameState::OMove)
+    }
+
+    /// `true` once the game has reached a terminal outcome.
+    fn is_concluded(&self) -> bool {
+        matches!(self, GameState::XWon | GameState::OWon | GameState::Draw)
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Board {
     pub winner_address: Pubkey,
     pub player_x: Pubkey,
     pub player_o: Pubkey,
+    /// Wallet that has requested to join as Player O but has not yet been
+    /// accepted by Player X. `Pubkey::default()` when no request is pending.
+    pub pending_o: Pubkey,
     pub current_player: Pubkey,
-    /// The game board state as a flat array of 9 cells (0=empty, 1=X, 2=O)
-    /// Layout: [0,1,2,3,4,5, 6,7,8] represents a 3x3 grid
-    pub board: [u8; BOARD_SIZE],
-    pub is_active: bool,
+    /// Flat row-major board storage (0=empty, 1=X, 2=O), length
+    /// `board_dim * board_dim`, capped at `MAX_CELLS`.
+    #[max_len(MAX_CELLS)]
+    pub board: Vec<u8>,
+    /// Edge length of the square board.
+    pub board_dim: u8,
+    /// Number of consecutive matching marks required to win.
+    pub win_len: u8,
+    pub state: GameState,
     /// Unique identifier for this game (incremented per player X)
     pub game_id: u64,
+    /// Unix timestamp (from `Clock`) of the last move made on this board, or
+    /// of its creation, start or restart if no move has been made since;
+    /// used to let the waiting player claim a timeout forfeit.
+    pub last_move_ts: i64,
 }
 
 /// Account structure tracking the number of games created by a user.
@@ -330,11 +568,40 @@ pub struct UserGameCounter {
     pub game_count: u64,
 }
 
+/// Singleton PDA indexing overall activity across every game, independent
+/// of any single user's `UserGameCounter`. Only maintained by L1-only
+/// instructions (`initialize`), since the instructions that run post-delegation
+/// (`make_move`, `claim_timeout`, `restart`) execute on the ephemeral rollup
+/// and can't write this non-delegated account.
+#[account]
+#[derive(InitSpace)]
+pub struct Dashboard {
+    /// Total number of games ever created.
+    pub game_count: u64,
+    /// Board PDA of the most recently created game.
+    pub latest_game: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDashboard<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Dashboard::INIT_SPACE,
+        seeds = [b"dashboard"],
+        bump
+    )]
+    pub dashboard: Account<'info, Dashboard>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
      init_if_needed,
-     payer = payer, 
+     payer = payer,
      space = 8 + UserGameCounter::INIT_SPACE,
      seeds=[b"user_games",payer.key().as_ref()],
      bump
@@ -342,12 +609,16 @@ pub struct Initialize<'info> {
     pub user_games: Account<'info, UserGameCounter>,
 
     #[account(init,
-     payer = payer, 
+     payer = payer,
      space = 8 + Board::INIT_SPACE,
      seeds=[b"board",payer.key().as_ref(),&user_games.game_count.to_le_bytes()],
      bump
     )]
     pub board_account: Account<'info, Board>,
+
+    #[account(mut, seeds = [b"dashboard"], bump)]
+    pub dashboard: Account<'info, Dashboard>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -359,8 +630,33 @@ pub struct RegisterPlayerO<'info> {
 
     #[account(
         mut,
-        constraint = board_account.is_active == true @ TicTacError::GameOver,
-        constraint = board_account.player_o == Pubkey::default() @ TicTacError::PlayerAlreadyRegistered
+        constraint = board_account.state == GameState::Waiting @ TicTacError::GameOver,
+        constraint = board_account.player_o == Pubkey::default() @ TicTacError::PlayerAlreadyRegistered,
+        constraint = board_account.pending_o == Pubkey::default() @ TicTacError::PlayerAlreadyRegistered
+    )]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPlayerO<'info> {
+    #[account(constraint = player_x.key() == board_account.player_x @ TicTacError::Unauthorised)]
+    pub player_x: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = board_account.state == GameState::Waiting @ TicTacError::GameOver
+    )]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct RejectPlayerO<'info> {
+    #[account(constraint = player_x.key() == board_account.player_x @ TicTacError::Unauthorised)]
+    pub player_x: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = board_account.state == GameState::Waiting @ TicTacError::GameOver
     )]
     pub board_account: Account<'info, Board>,
 }
@@ -374,17 +670,43 @@ pub struct PlayerOJoin<'info> {
     pub board_account: Account<'info, Board>,
 }
 
+#[derive(Accounts)]
+pub struct ClaimTimeout<'info> {
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = board_account.state.is_active() @ TicTacError::GameOver,
+        constraint = (claimant.key() == board_account.player_x || claimant.key() == board_account.player_o)
+            @ TicTacError::Unauthorised,
+        constraint = board_account.current_player != claimant.key() @ TicTacError::NotYourChance
+    )]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct Restart<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = board_account.state.is_concluded() @ TicTacError::GameStillActive,
+        constraint = (signer.key() == board_account.player_x || signer.key() == board_account.player_o)
+            @ TicTacError::Unauthorised
+    )]
+    pub board_account: Account<'info, Board>,
+}
+
 #[derive(Accounts)]
 pub struct PlayerMove<'info> {
     pub player: Signer<'info>,
 
     #[account(
         mut,
-        constraint = board_account.is_active == true @ TicTacError::GameOver,
+        constraint = board_account.state.is_active() @ TicTacError::GameOver,
         constraint = board_account.current_player == player.key() @ TicTacError::NotYourChance,
-        constraint = board_account.player_o != Pubkey::default() @ TicTacError::PlayerONotRegistered,
-        constraint = (board_account.current_player == board_account.player_x || 
-            board_account.current_player == board_account.player_o) 
+        constraint = (board_account.current_player == board_account.player_x ||
+            board_account.current_player == board_account.player_o)
            @ TicTacError::Unauthorised
     )]
     pub board_account: Account<'info, Board>,